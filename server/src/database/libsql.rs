@@ -1,31 +1,173 @@
-use std::path::Path;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::str::FromStr;
-#[cfg(feature = "mwal_backend")]
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
 use std::time::{Duration, Instant};
 
+use futures::{Stream, StreamExt};
+use tokio_stream::wrappers::BroadcastStream;
+
 use crossbeam::channel::RecvTimeoutError;
 use rusqlite::{params_from_iter, OpenFlags};
 use tokio::sync::oneshot;
 use tracing::warn;
 
+use crate::libsql::session::Session;
 use crate::libsql::wal_hook::WalHook;
 use crate::libsql::WalConnection;
 use crate::query::{
     Column, ErrorCode, QueryError, QueryResponse, QueryResult, ResultSet, Row, Value,
 };
-use crate::query_analysis::{State, Statement};
+use crate::query_analysis::{State, Statement, StmtKind};
 
 use super::{Database, TXN_TIMEOUT_SECS};
 
+enum ReadMessage {
+    Execute(Statement, Vec<Value>, oneshot::Sender<QueryResult>),
+    ExecuteStream(
+        Statement,
+        Vec<Value>,
+        usize,
+        tokio::sync::mpsc::UnboundedSender<StreamResult>,
+    ),
+    ReadBlob(BlobRead, oneshot::Sender<BlobResult>),
+}
+
+/// A precondition checked on the connection thread before a batch is allowed to commit.
+/// `stmt` must be a read-only statement; the batch is rolled back unless it returns at
+/// least one row.
+pub struct BatchCheck {
+    pub stmt: Statement,
+    pub params: Vec<Value>,
+}
+
+/// Outcome of an `execute_batch` call: either the per-statement results of every
+/// statement in the batch (all committed together), or the reason the whole batch was
+/// rolled back.
+pub type BatchResult = Result<Vec<QueryResult>, QueryError>;
+
+/// Number of pages copied per `sqlite3_backup_step` call before yielding the writer
+/// thread back to the message loop; keeps a single backup from starving live queries.
+const BACKUP_STEP_PAGES: i32 = 100;
+/// How long the backup loop waits between batches of `BACKUP_STEP_PAGES` pages; the
+/// wait is spent servicing other writer-thread messages (see `run_backup`).
+const BACKUP_STEP_SLEEP: Duration = Duration::from_millis(50);
+
+/// Progress of a running backup, reported after each batch of pages is copied.
+#[derive(Debug, Clone, Copy)]
+pub struct BackupProgress {
+    pub remaining: i32,
+    pub total: i32,
+}
+
+pub type BackupResult = Result<(), QueryError>;
+
+/// Capacity of the broadcast channel changesets are published on; a slow subscriber
+/// that falls this far behind the writer starts missing changesets rather than
+/// applying backpressure to live writes.
+const CHANGESET_CHANNEL_CAPACITY: usize = 1024;
+
+/// Capacity of the broadcast channel row-level change notifications are published on.
+const CHANGE_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A single row-level change, published once the transaction that made it commits.
+#[derive(Debug, Clone)]
+pub struct ChangeEvent {
+    pub table: String,
+    pub rowid: i64,
+    pub kind: ChangeKind,
+}
+
+/// One page of a streamed result set; `columns` is repeated on every page so a reader
+/// can consume pages independently of one another.
+pub type StreamResult = Result<ResultSet, QueryError>;
+
+/// Identifies a BLOB cell to read a slice of, addressed the same way
+/// `sqlite3_blob_open` addresses it: by table, column and rowid.
+pub struct BlobRead {
+    pub table: String,
+    pub column: String,
+    pub rowid: i64,
+    pub offset: usize,
+    pub len: usize,
+}
+
+pub type BlobResult = Result<Vec<u8>, QueryError>;
+
+enum Message {
+    Execute(Statement, Vec<Value>, oneshot::Sender<QueryResult>),
+    ExecuteBatch(
+        Vec<(Statement, Vec<Value>)>,
+        Vec<BatchCheck>,
+        oneshot::Sender<BatchResult>,
+    ),
+    Backup(
+        PathBuf,
+        tokio::sync::mpsc::UnboundedSender<BackupProgress>,
+        oneshot::Sender<BackupResult>,
+    ),
+    ExecuteStream(
+        Statement,
+        Vec<Value>,
+        usize,
+        tokio::sync::mpsc::UnboundedSender<StreamResult>,
+    ),
+    ReadBlob(BlobRead, oneshot::Sender<BlobResult>),
+}
+
 #[derive(Clone)]
 pub struct LibSqlDb {
-    sender: crossbeam::channel::Sender<(Statement, Vec<Value>, oneshot::Sender<QueryResult>)>,
+    sender: crossbeam::channel::Sender<Message>,
+    reader_sender: crossbeam::channel::Sender<ReadMessage>,
+    /// Set by the writer thread while a transaction spans more than one `execute` call,
+    /// so that `Database::execute` knows reads must not be routed to the reader pool
+    /// (they would not see the writer's uncommitted changes).
+    in_txn: Arc<AtomicBool>,
+    /// Publishes a changeset blob after every committed write; see `subscribe_changes`.
+    changes_tx: tokio::sync::broadcast::Sender<Vec<u8>>,
+    /// Publishes one `ChangeEvent` per row touched by a committed write; see `watch`.
+    events_tx: tokio::sync::broadcast::Sender<ChangeEvent>,
 }
 
 fn execute_query(conn: &rusqlite::Connection, stmt: &Statement, params: Vec<Value>) -> QueryResult {
-    let mut rows = vec![];
-    let mut prepared = conn.prepare(&stmt.stmt)?;
+    // A cache *hit* below skips `sqlite3_prepare_v2` entirely, so a stale cached plan's
+    // `SQLITE_SCHEMA` error can surface anywhere from `prepare_cached` through the last
+    // `qresult.next()` call, not just at prepare time. `run_to_completion` is retried as
+    // a whole rather than just the prepare, since nothing has been returned to the
+    // caller yet either way -- it's safe to drop every cached statement and run once
+    // more against a freshly compiled plan.
+    match run_to_completion(conn, &stmt.stmt, &params) {
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if e.code == rusqlite::ffi::ErrorCode::SchemaChanged =>
+        {
+            conn.flush_prepared_statement_cache();
+            run_to_completion(conn, &stmt.stmt, &params).map_err(Into::into)
+        }
+        result => result.map_err(Into::into),
+    }
+}
+
+/// Prepares `sql` (reusing the cache) and materializes every row into a `ResultSet`.
+fn run_to_completion(
+    conn: &rusqlite::Connection,
+    sql: &str,
+    params: &[Value],
+) -> Result<QueryResponse, rusqlite::Error> {
+    // `prepare_cached` reuses a compiled plan for identical SQL text instead of
+    // recompiling it on every call; the cache lives on the connection itself (see
+    // `set_prepared_statement_cache_capacity` in `open_db`/`open_reader`).
+    let mut prepared = conn.prepare_cached(sql)?;
     let columns = prepared
         .columns()
         .iter()
@@ -40,8 +182,9 @@ fn execute_query(conn: &rusqlite::Connection, stmt: &Statement, params: Vec<Valu
         })
         .collect::<Vec<_>>();
     let mut qresult = prepared.query(params_from_iter(
-        params.into_iter().map(rusqlite::types::Value::from),
+        params.iter().cloned().map(rusqlite::types::Value::from),
     ))?;
+    let mut rows = vec![];
     while let Some(row) = qresult.next()? {
         let mut values = vec![];
         for (i, _) in columns.iter().enumerate() {
@@ -53,11 +196,388 @@ fn execute_query(conn: &rusqlite::Connection, stmt: &Statement, params: Vec<Valu
     Ok(QueryResponse::ResultSet(ResultSet { columns, rows }))
 }
 
+/// Like `execute_query`, but sends `ResultSet` pages of at most `page_size` rows over
+/// `tx` as they are produced instead of materializing the whole result in memory. The
+/// final page (possibly empty) signals the end of the stream; `tx` is never closed
+/// explicitly, the caller just stops polling once it is dropped.
+fn execute_query_stream(
+    conn: &rusqlite::Connection,
+    stmt: &Statement,
+    params: Vec<Value>,
+    page_size: usize,
+    tx: &tokio::sync::mpsc::UnboundedSender<StreamResult>,
+) {
+    let mut prepared = match conn.prepare_cached(&stmt.stmt) {
+        Ok(prepared) => prepared,
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if e.code == rusqlite::ffi::ErrorCode::SchemaChanged =>
+        {
+            conn.flush_prepared_statement_cache();
+            match conn.prepare_cached(&stmt.stmt) {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    let _ = tx.send(Err(e.into()));
+                    return;
+                }
+            }
+        }
+        Err(e) => {
+            let _ = tx.send(Err(e.into()));
+            return;
+        }
+    };
+    let columns = prepared
+        .columns()
+        .iter()
+        .map(|col| Column {
+            name: col.name().into(),
+            ty: col
+                .decl_type()
+                .map(FromStr::from_str)
+                .transpose()
+                .ok()
+                .flatten(),
+        })
+        .collect::<Vec<_>>();
+    let mut qresult = match prepared.query(params_from_iter(
+        params.iter().cloned().map(rusqlite::types::Value::from),
+    )) {
+        Ok(qresult) => qresult,
+        Err(e) => {
+            let _ = tx.send(Err(e.into()));
+            return;
+        }
+    };
+
+    // A cache *hit* above skips `sqlite3_prepare_v2` entirely, so a stale cached plan's
+    // `SQLITE_SCHEMA` error can also surface here, on the very first step, rather than
+    // only at `prepare_cached`. No page has been sent to `tx` yet at this point, so it
+    // is still safe to flush the cache and retry the whole statement once against a
+    // freshly compiled plan; past the first row, retrying would mean resending pages
+    // the caller already has.
+    let mut first = qresult.next();
+    if let Err(rusqlite::Error::SqliteFailure(e, _)) = &first {
+        if e.code == rusqlite::ffi::ErrorCode::SchemaChanged {
+            conn.flush_prepared_statement_cache();
+            prepared = match conn.prepare_cached(&stmt.stmt) {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    let _ = tx.send(Err(e.into()));
+                    return;
+                }
+            };
+            qresult = match prepared.query(params_from_iter(
+                params.into_iter().map(rusqlite::types::Value::from),
+            )) {
+                Ok(qresult) => qresult,
+                Err(e) => {
+                    let _ = tx.send(Err(e.into()));
+                    return;
+                }
+            };
+            first = qresult.next();
+        }
+    }
+
+    let mut rows = Vec::with_capacity(page_size);
+    if let StreamStep::Stop = handle_stream_step(first, &columns, &mut rows, page_size, tx) {
+        return;
+    }
+    loop {
+        if let StreamStep::Stop = handle_stream_step(qresult.next(), &columns, &mut rows, page_size, tx)
+        {
+            return;
+        }
+    }
+}
+
+/// Whether the caller of `handle_stream_step` should keep pulling rows from `qresult`.
+enum StreamStep {
+    Continue,
+    Stop,
+}
+
+/// Turns one `qresult.next()` outcome into zero or more pages sent over `tx`, pushing
+/// the row into `rows` and flushing a full page when it reaches `page_size`.
+fn handle_stream_step<'stmt>(
+    step: Result<Option<rusqlite::Row<'stmt>>, rusqlite::Error>,
+    columns: &[Column],
+    rows: &mut Vec<Row>,
+    page_size: usize,
+    tx: &tokio::sync::mpsc::UnboundedSender<StreamResult>,
+) -> StreamStep {
+    match step {
+        Ok(Some(row)) => {
+            let mut values = Vec::with_capacity(columns.len());
+            for (i, _) in columns.iter().enumerate() {
+                match row.get::<usize, rusqlite::types::Value>(i) {
+                    Ok(v) => values.push(v.into()),
+                    Err(e) => {
+                        let _ = tx.send(Err(e.into()));
+                        return StreamStep::Stop;
+                    }
+                }
+            }
+            rows.push(Row { values });
+            if rows.len() >= page_size {
+                let page = std::mem::replace(rows, Vec::with_capacity(page_size));
+                let page = ResultSet {
+                    columns: columns.to_vec(),
+                    rows: page,
+                };
+                if tx.send(Ok(page)).is_err() {
+                    return StreamStep::Stop;
+                }
+            }
+            StreamStep::Continue
+        }
+        Ok(None) => {
+            let _ = tx.send(Ok(ResultSet {
+                columns: columns.to_vec(),
+                rows: std::mem::take(rows),
+            }));
+            StreamStep::Stop
+        }
+        Err(e) => {
+            let _ = tx.send(Err(e.into()));
+            StreamStep::Stop
+        }
+    }
+}
+
+/// Reads up to `req.len` bytes of a BLOB cell starting at `req.offset`, using SQLite's
+/// incremental-BLOB-read API so the whole value never has to be materialized to answer
+/// a request for one slice of it.
+fn read_blob_slice(conn: &rusqlite::Connection, req: &BlobRead) -> BlobResult {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut blob = conn.blob_open(
+        rusqlite::DatabaseName::Main,
+        &req.table,
+        &req.column,
+        req.rowid,
+        true,
+    )?;
+    blob.seek(SeekFrom::Start(req.offset as u64))
+        .map_err(|e| QueryError::new(ErrorCode::Internal, e.to_string()))?;
+    // Cap the read to what's actually left in the blob so a caller-supplied `len` can't
+    // drive an allocation far larger than the value being read.
+    let remaining = (blob.size() as u64).saturating_sub(req.offset as u64) as usize;
+    let mut buf = vec![0u8; req.len.min(remaining)];
+    let n = blob
+        .read(&mut buf)
+        .map_err(|e| QueryError::new(ErrorCode::Internal, e.to_string()))?;
+    buf.truncate(n);
+    Ok(buf)
+}
+
 fn rollback(conn: &rusqlite::Connection) {
     conn.execute("rollback transaction;", ())
         .expect("failed to rollback");
 }
 
+/// Runs `stmts` inside a single `BEGIN IMMEDIATE`/`COMMIT`, first verifying that every
+/// `checks` statement returns at least one row. If any check fails, or any statement
+/// errors, the whole batch is rolled back and nothing is committed.
+fn execute_batch(
+    conn: &rusqlite::Connection,
+    stmts: Vec<(Statement, Vec<Value>)>,
+    checks: Vec<BatchCheck>,
+) -> BatchResult {
+    conn.execute("begin immediate;", ())?;
+
+    for check in checks {
+        match execute_query(conn, &check.stmt, check.params) {
+            Ok(QueryResponse::ResultSet(ResultSet { rows, .. })) if !rows.is_empty() => (),
+            Ok(_) => {
+                rollback(conn);
+                return Err(QueryError::new(
+                    ErrorCode::TxBusy,
+                    "batch precondition check failed",
+                ));
+            }
+            Err(e) => {
+                rollback(conn);
+                return Err(e);
+            }
+        }
+    }
+
+    let mut results = Vec::with_capacity(stmts.len());
+    for (stmt, params) in stmts {
+        match execute_query(conn, &stmt, params) {
+            Ok(resp) => results.push(Ok(resp)),
+            Err(e) => {
+                rollback(conn);
+                return Err(e);
+            }
+        }
+    }
+
+    if let Err(e) = conn.execute("commit;", ()) {
+        rollback(conn);
+        return Err(e.into());
+    }
+    Ok(results)
+}
+
+/// Copies the database to `dst_path` using SQLite's online backup API, running on the
+/// same thread that owns the writer connection so the copy is transactionally
+/// consistent without ever blocking on or being blocked by live queries. Progress is
+/// reported on `progress` after every batch of `BACKUP_STEP_PAGES` pages.
+///
+/// Unlike a plain step-then-sleep loop, the gap between batches is spent servicing
+/// `receiver` via `dispatch` instead of just sleeping: otherwise every other write,
+/// `execute_batch`, and `read_blob` on the writer thread would queue up behind the
+/// *entire* backup instead of just behind one batch of pages, which is exactly what
+/// bounding the batch size was supposed to avoid. A second, concurrent backup request
+/// is the only message turned away outright. Returns `ControlFlow::Break(())` if a
+/// message response could not be delivered (the caller's receiver went away) or if
+/// `receiver` is disconnected, mirroring `dispatch`'s own signal for "the writer thread
+/// should stop".
+fn run_backup(
+    conn: &rusqlite::Connection,
+    dst_path: &Path,
+    progress: &tokio::sync::mpsc::UnboundedSender<BackupProgress>,
+    receiver: &crossbeam::channel::Receiver<Message>,
+    state: &mut State,
+    timeout_deadline: &mut Option<Instant>,
+    timedout: &mut bool,
+    in_txn: &Arc<AtomicBool>,
+) -> ControlFlow<(), BackupResult> {
+    macro_rules! try_backup {
+        ($e:expr) => {
+            match $e {
+                Ok(v) => v,
+                Err(e) => return ControlFlow::Continue(Err(e.into())),
+            }
+        };
+    }
+
+    let mut dst = try_backup!(rusqlite::Connection::open(dst_path));
+    let backup = try_backup!(rusqlite::backup::Backup::new(conn, &mut dst));
+
+    loop {
+        let step_result = try_backup!(backup.step(BACKUP_STEP_PAGES));
+        let p = backup.progress();
+        let _ = progress.send(BackupProgress {
+            remaining: p.remaining,
+            total: p.pagecount,
+        });
+        if step_result == rusqlite::backup::StepResult::Done {
+            return ControlFlow::Continue(Ok(()));
+        }
+
+        let step_deadline = Instant::now() + BACKUP_STEP_SLEEP;
+        loop {
+            let (deadline, deadline_is_txn) = match *timeout_deadline {
+                Some(txn_deadline) if txn_deadline < step_deadline => (txn_deadline, true),
+                _ => (step_deadline, false),
+            };
+            match receiver.recv_deadline(deadline) {
+                Ok(Message::Backup(_, _, nested_sender)) => {
+                    let _ = nested_sender.send(Err(QueryError::new(
+                        ErrorCode::TxBusy,
+                        "a backup is already in progress",
+                    )));
+                }
+                Ok(msg) => dispatch(msg, conn, state, timeout_deadline, timedout, in_txn)?,
+                Err(RecvTimeoutError::Timeout) if deadline_is_txn => {
+                    warn!("transaction timed out");
+                    rollback(conn);
+                    *timeout_deadline = None;
+                    *timedout = true;
+                    *state = State::Start;
+                    in_txn.store(false, Ordering::Relaxed);
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return ControlFlow::Break(()),
+            }
+        }
+    }
+}
+
+/// Drains `session` of everything it has accumulated since the last commit and
+/// publishes it as a changeset blob, then resets the session so it starts tracking the
+/// next transaction from a clean slate. Called only at commit boundaries (never after a
+/// rollback), so subscribers never see a changeset for a transaction that didn't stick.
+fn publish_changeset(session: &mut Session, changes_tx: &tokio::sync::broadcast::Sender<Vec<u8>>) {
+    if session.is_empty() {
+        return;
+    }
+    match session.changeset() {
+        Ok(changeset) => {
+            let _ = changes_tx.send(changeset);
+        }
+        Err(e) => warn!("failed to capture changeset: {}", e),
+    }
+    session.reset();
+}
+
+/// Applies a changeset produced by `publish_changeset` to `conn`, calling
+/// `on_conflict` for every conflicting row to decide whether to resolve it by
+/// OMIT/REPLACE/ABORT; see `crate::libsql::session::apply_changeset`.
+pub fn apply_changeset(
+    conn: &rusqlite::Connection,
+    changeset: &[u8],
+    on_conflict: impl FnMut(
+        crate::libsql::session::ConflictKind,
+        &crate::libsql::session::ChangesetItem,
+    ) -> crate::libsql::session::ConflictAction,
+) -> anyhow::Result<()> {
+    crate::libsql::session::apply_changeset(conn, changeset, on_conflict)
+}
+
+/// Registers SQLite's update and commit/rollback hooks on `conn`. The update hook
+/// buffers row-level changes per-transaction; the commit hook is the single source of
+/// truth for "this transaction actually committed" and is where both chunk0-6's
+/// row-level notifications are flushed and chunk0-5's changeset is captured and
+/// published, via `publish_changeset`. Driving both off the real commit/rollback hooks
+/// (rather than inferring commit-vs-rollback from `State` transitions, which cannot
+/// tell an explicit client `ROLLBACK` from a `COMMIT`) is what guarantees a rolled-back
+/// transaction — including the timeout rollback path — never gets reported as
+/// committed to either subscriber.
+fn register_write_hooks(
+    conn: &rusqlite::Connection,
+    session: Arc<Mutex<Session>>,
+    changes_tx: tokio::sync::broadcast::Sender<Vec<u8>>,
+    events_tx: tokio::sync::broadcast::Sender<ChangeEvent>,
+) {
+    let buffer: Arc<Mutex<Vec<ChangeEvent>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let update_buffer = buffer.clone();
+    conn.update_hook(Some(
+        move |action: rusqlite::hooks::Action, _db: &str, table: &str, rowid: i64| {
+            let kind = match action {
+                rusqlite::hooks::Action::SQLITE_INSERT => ChangeKind::Insert,
+                rusqlite::hooks::Action::SQLITE_UPDATE => ChangeKind::Update,
+                rusqlite::hooks::Action::SQLITE_DELETE => ChangeKind::Delete,
+                _ => return,
+            };
+            update_buffer.lock().unwrap().push(ChangeEvent {
+                table: table.to_string(),
+                rowid,
+                kind,
+            });
+        },
+    ));
+
+    let commit_buffer = buffer.clone();
+    let commit_session = session.clone();
+    conn.commit_hook(Some(move || {
+        for event in commit_buffer.lock().unwrap().drain(..) {
+            let _ = events_tx.send(event);
+        }
+        publish_changeset(&mut commit_session.lock().unwrap(), &changes_tx);
+        false
+    }));
+
+    conn.rollback_hook(Some(move || {
+        buffer.lock().unwrap().clear();
+        session.lock().unwrap().reset();
+    }));
+}
+
 macro_rules! ok_or_exit {
     ($e:expr) => {
         if let Err(_) = $e {
@@ -66,15 +586,155 @@ macro_rules! ok_or_exit {
     };
 }
 
+macro_rules! ok_or_break {
+    ($e:expr) => {
+        if let Err(_) = $e {
+            return ControlFlow::Break(());
+        }
+    };
+}
+
+/// Handles every writer-thread message except `Backup`, which needs `receiver` itself
+/// to interleave backup progress with other work (see `run_backup`). Factored out of
+/// the main dispatch loop so the two can share it: a long-running backup must keep
+/// servicing writes and reads between page batches instead of blocking them for its
+/// entire duration. Returns `ControlFlow::Break(())` if a response could not be
+/// delivered (the caller's receiver went away), signalling that the writer thread
+/// should stop.
+fn dispatch(
+    msg: Message,
+    conn: &rusqlite::Connection,
+    state: &mut State,
+    timeout_deadline: &mut Option<Instant>,
+    timedout: &mut bool,
+    in_txn: &Arc<AtomicBool>,
+) -> ControlFlow<()> {
+    match msg {
+        Message::Execute(stmt, params, sender) => {
+            if !*timedout {
+                let old_state = *state;
+                let result = execute_query(conn, &stmt, params);
+                if result.is_ok() {
+                    state.step(stmt.kind);
+                    match (old_state, *state) {
+                        (State::Start, State::TxnOpened) => {
+                            timeout_deadline
+                                .replace(Instant::now() + Duration::from_secs(TXN_TIMEOUT_SECS));
+                            in_txn.store(true, Ordering::Relaxed);
+                        }
+                        (State::TxnOpened, State::TxnClosed) => {
+                            timeout_deadline.take();
+                            state.reset();
+                            in_txn.store(false, Ordering::Relaxed);
+                        }
+                        (_, State::Invalid) => panic!("invalid state"),
+                        _ => (),
+                    }
+                }
+                ok_or_break!(sender.send(result));
+            } else {
+                ok_or_break!(sender.send(Err(QueryError::new(
+                    ErrorCode::TxTimeout,
+                    "transaction timedout",
+                ))));
+                *timedout = false;
+            }
+        }
+        Message::ExecuteBatch(stmts, checks, sender) => {
+            if *timedout {
+                ok_or_break!(sender.send(Err(QueryError::new(
+                    ErrorCode::TxTimeout,
+                    "transaction timedout",
+                ))));
+                *timedout = false;
+            } else {
+                match *state {
+                    State::Start => {
+                        let result = execute_batch(conn, stmts, checks);
+                        ok_or_break!(sender.send(result));
+                    }
+                    _ => {
+                        ok_or_break!(sender.send(Err(QueryError::new(
+                            ErrorCode::TxBusy,
+                            "cannot run a batch while a transaction is open",
+                        ))));
+                    }
+                }
+            }
+        }
+        Message::ExecuteStream(stmt, params, page_size, tx) => {
+            if *timedout {
+                let _ = tx.send(Err(QueryError::new(
+                    ErrorCode::TxTimeout,
+                    "transaction timedout",
+                )));
+                *timedout = false;
+            } else {
+                execute_query_stream(conn, &stmt, params, page_size, &tx);
+            }
+        }
+        Message::ReadBlob(req, sender) => {
+            if *timedout {
+                ok_or_break!(sender.send(Err(QueryError::new(
+                    ErrorCode::TxTimeout,
+                    "transaction timedout",
+                ))));
+                *timedout = false;
+            } else {
+                ok_or_break!(sender.send(read_blob_slice(conn, &req)));
+            }
+        }
+        // Only reachable if a caller ever routes a `Backup` message through here
+        // directly; `run_backup`'s own gap-filling loop intercepts and rejects a
+        // second concurrent backup before it would reach `dispatch`. Kept so the match
+        // stays exhaustive over `Message`.
+        Message::Backup(_, _, sender) => {
+            ok_or_break!(sender.send(Err(QueryError::new(
+                ErrorCode::TxBusy,
+                "cannot back up while a transaction is open",
+            ))));
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+/// > When the last connection to a particular database is closing, that connection
+/// > will acquire an exclusive lock for a short time while it cleans up the WAL and
+/// > shared-memory files. If a second database tries to open and query the database
+/// > while the first connection is still in the middle of its cleanup process, the
+/// > second connection might get an SQLITE_BUSY error.
+///
+/// For this reason any connection open (writer or reader) may not succeed right away,
+/// so `open` is retried a couple of times before giving up. `open` is called again from
+/// scratch on every retry, since the failed attempt may have left nothing usable behind.
+fn open_with_busy_retry<T>(mut open: impl FnMut() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    let mut retries = 0;
+    loop {
+        match open() {
+            Ok(conn) => return Ok(conn),
+            Err(e) => match e.downcast::<rusqlite::Error>() {
+                Ok(rusqlite::Error::SqliteFailure(e, _))
+                    if e.code == rusqlite::ffi::ErrorCode::DatabaseBusy && retries < 10 =>
+                {
+                    std::thread::sleep(Duration::from_millis(10));
+                    retries += 1;
+                }
+                Ok(e) => panic!("Unhandled error opening libsql: {}", e),
+                Err(e) => panic!("Unhandled error opening libsql: {}", e),
+            },
+        }
+    }
+}
+
 fn open_db(
     path: impl AsRef<Path> + Send + 'static,
     #[cfg(feature = "mwal_backend")] vwal_methods: Option<
         Arc<Mutex<mwal::ffi::libsql_wal_methods>>,
     >,
     wal_hook: impl WalHook + Send + Clone + 'static,
+    stmt_cache_capacity: usize,
 ) -> anyhow::Result<WalConnection> {
-    let mut retries = 0;
-    loop {
+    let conn = open_with_busy_retry(|| {
         #[cfg(feature = "mwal_backend")]
         let conn_result = match vwal_methods {
             Some(ref vwal_methods) => crate::libsql::mwal::open_with_virtual_wal(
@@ -103,28 +763,73 @@ fn open_db(
                 | OpenFlags::SQLITE_OPEN_NO_MUTEX,
             wal_hook.clone(),
         );
+        conn_result
+    })?;
+    conn.set_prepared_statement_cache_capacity(stmt_cache_capacity);
+    Ok(conn)
+}
 
-        match conn_result {
-            Ok(conn) => return Ok(conn),
-            Err(e) => {
-                match e.downcast::<rusqlite::Error>() {
-                    // > When the last connection to a particular database is closing, that
-                    // > connection will acquire an exclusive lock for a short time while it cleans
-                    // > up the WAL and shared-memory files. If a second database tries to open and
-                    // > query the database while the first connection is still in the middle of its
-                    // > cleanup process, the second connection might get an SQLITE_BUSY error.
-                    //
-                    // For this reason we may not be able to open the database right away, so we
-                    // retry a couple of times before giving up.
-                    Ok(rusqlite::Error::SqliteFailure(e, _))
-                        if e.code == rusqlite::ffi::ErrorCode::DatabaseBusy && retries < 10 =>
-                    {
-                        std::thread::sleep(Duration::from_millis(10));
-                        retries += 1;
-                    }
-                    Ok(e) => panic!("Unhandled error opening libsql: {}", e),
-                    Err(e) => panic!("Unhandled error opening libsql: {}", e),
-                }
+/// Opens a read-only connection used by the reader pool. Under the regular WAL backend
+/// readers skip the writer's WAL-hook machinery and just open the file directly; WAL
+/// mode lets them run concurrently with the writer and with each other. Under
+/// `mwal_backend`, storage itself lives behind the virtual WAL, so a plain file open
+/// would not see the writer's data at all -- readers have to go through
+/// `open_with_virtual_wal` too, just with read-only flags.
+fn open_reader(
+    path: impl AsRef<Path>,
+    #[cfg(feature = "mwal_backend")] vwal_methods: Option<
+        Arc<Mutex<mwal::ffi::libsql_wal_methods>>,
+    >,
+    stmt_cache_capacity: usize,
+) -> anyhow::Result<rusqlite::Connection> {
+    let conn = open_with_busy_retry(|| {
+        #[cfg(feature = "mwal_backend")]
+        let conn_result = match vwal_methods {
+            Some(ref vwal_methods) => crate::libsql::mwal::open_with_virtual_wal(
+                &path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY
+                    | OpenFlags::SQLITE_OPEN_URI
+                    | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+                vwal_methods.clone(),
+            ),
+            None => rusqlite::Connection::open_with_flags(
+                &path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY
+                    | OpenFlags::SQLITE_OPEN_URI
+                    | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )
+            .map_err(anyhow::Error::from),
+        };
+        #[cfg(not(feature = "mwal_backend"))]
+        let conn_result = rusqlite::Connection::open_with_flags(
+            &path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_URI
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(anyhow::Error::from);
+        conn_result
+    })?;
+    conn.set_prepared_statement_cache_capacity(stmt_cache_capacity);
+    Ok(conn)
+}
+
+/// Body of a reader-pool worker thread: pulls read-only statements off `receiver` and
+/// runs them on its own connection, independently of the writer and of the other
+/// readers.
+fn run_reader(conn: rusqlite::Connection, receiver: crossbeam::channel::Receiver<ReadMessage>) {
+    while let Ok(msg) = receiver.recv() {
+        match msg {
+            ReadMessage::Execute(stmt, params, sender) => {
+                let result = execute_query(&conn, &stmt, params);
+                ok_or_exit!(sender.send(result));
+            }
+            ReadMessage::ExecuteStream(stmt, params, page_size, tx) => {
+                execute_query_stream(&conn, &stmt, params, page_size, &tx);
+            }
+            ReadMessage::ReadBlob(req, sender) => {
+                let result = read_blob_slice(&conn, &req);
+                ok_or_exit!(sender.send(result));
             }
         }
     }
@@ -132,37 +837,69 @@ fn open_db(
 
 impl LibSqlDb {
     pub fn new(
-        path: impl AsRef<Path> + Send + 'static,
+        path: impl AsRef<Path> + Send + Clone + 'static,
         #[cfg(feature = "mwal_backend")] vwal_methods: Option<
             Arc<Mutex<mwal::ffi::libsql_wal_methods>>,
         >,
         wal_hook: impl WalHook + Send + Clone + 'static,
+        reader_pool_size: usize,
+        stmt_cache_capacity: usize,
     ) -> anyhow::Result<Self> {
-        let (sender, receiver) =
-            crossbeam::channel::unbounded::<(Statement, Vec<Value>, oneshot::Sender<QueryResult>)>(
-            );
+        let (sender, receiver) = crossbeam::channel::unbounded::<Message>();
+        let (reader_sender, reader_receiver) = crossbeam::channel::unbounded::<ReadMessage>();
+        let in_txn = Arc::new(AtomicBool::new(false));
+        let (changes_tx, _) =
+            tokio::sync::broadcast::channel::<Vec<u8>>(CHANGESET_CHANNEL_CAPACITY);
+        let (events_tx, _) =
+            tokio::sync::broadcast::channel::<ChangeEvent>(CHANGE_EVENT_CHANNEL_CAPACITY);
 
+        for _ in 0..reader_pool_size {
+            let reader_receiver = reader_receiver.clone();
+            let reader_conn = open_reader(
+                path.clone(),
+                #[cfg(feature = "mwal_backend")]
+                vwal_methods.clone(),
+                stmt_cache_capacity,
+            )?;
+            tokio::task::spawn_blocking(move || run_reader(reader_conn, reader_receiver));
+        }
+
+        let writer_in_txn = in_txn.clone();
+        let writer_changes_tx = changes_tx.clone();
+        let writer_events_tx = events_tx.clone();
         tokio::task::spawn_blocking(move || {
             let conn = open_db(
                 path,
                 #[cfg(feature = "mwal_backend")]
                 vwal_methods,
                 wal_hook,
+                stmt_cache_capacity,
             )
             .unwrap();
+            // The session must exist before the first tracked write, so it is created
+            // up front and attached to every table in the database.
+            let mut session = Session::new(&conn).expect("failed to create session");
+            session
+                .attach_all()
+                .expect("failed to attach session to all tables");
+            let session = Arc::new(Mutex::new(session));
+            register_write_hooks(&conn, session, writer_changes_tx, writer_events_tx);
             let mut state = State::Start;
             let mut timeout_deadline = None;
             let mut timedout = false;
             loop {
-                let (stmt, params, sender) = match timeout_deadline {
+                let msg = match timeout_deadline {
                     Some(deadline) => match receiver.recv_deadline(deadline) {
                         Ok(msg) => msg,
                         Err(RecvTimeoutError::Timeout) => {
                             warn!("transaction timed out");
+                            // A real ROLLBACK fires the rollback hook registered above,
+                            // which drops the session's pending changeset for us.
                             rollback(&conn);
                             timeout_deadline = None;
                             timedout = true;
                             state = State::Start;
+                            writer_in_txn.store(false, Ordering::Relaxed);
                             continue;
                         }
                         Err(RecvTimeoutError::Disconnected) => break,
@@ -173,47 +910,366 @@ impl LibSqlDb {
                     },
                 };
 
-                if !timedout {
-                    let old_state = state;
-                    let result = execute_query(&conn, &stmt, params);
-                    if result.is_ok() {
-                        state.step(stmt.kind);
-                        match (old_state, state) {
-                            (State::Start, State::TxnOpened) => {
-                                timeout_deadline.replace(
-                                    Instant::now() + Duration::from_secs(TXN_TIMEOUT_SECS),
-                                );
-                            }
-                            (State::TxnOpened, State::TxnClosed) => {
-                                timeout_deadline.take();
-                                state.reset();
+                match msg {
+                    // `Backup` needs `receiver` itself to interleave progress with
+                    // other messages, so it bypasses `dispatch` and is driven here
+                    // directly; every other variant shares the same handling whether
+                    // it arrives in this loop or during a backup's gap between batches.
+                    Message::Backup(dst_path, progress, sender) => {
+                        if timedout {
+                            ok_or_exit!(sender.send(Err(QueryError::new(
+                                ErrorCode::TxTimeout,
+                                "transaction timedout",
+                            ))));
+                            timedout = false;
+                        } else {
+                            match state {
+                                State::Start => match run_backup(
+                                    &conn,
+                                    &dst_path,
+                                    &progress,
+                                    &receiver,
+                                    &mut state,
+                                    &mut timeout_deadline,
+                                    &mut timedout,
+                                    &writer_in_txn,
+                                ) {
+                                    ControlFlow::Continue(result) => {
+                                        ok_or_exit!(sender.send(result));
+                                    }
+                                    ControlFlow::Break(()) => break,
+                                },
+                                _ => {
+                                    ok_or_exit!(sender.send(Err(QueryError::new(
+                                        ErrorCode::TxBusy,
+                                        "cannot back up while a transaction is open",
+                                    ))));
+                                }
                             }
-                            (_, State::Invalid) => panic!("invalid state"),
-                            _ => (),
                         }
                     }
-                    ok_or_exit!(sender.send(result));
-                } else {
-                    ok_or_exit!(sender.send(Err(QueryError::new(
-                        ErrorCode::TxTimeout,
-                        "transaction timedout",
-                    ))));
-                    timedout = false;
+                    msg => {
+                        if let ControlFlow::Break(()) = dispatch(
+                            msg,
+                            &conn,
+                            &mut state,
+                            &mut timeout_deadline,
+                            &mut timedout,
+                            &writer_in_txn,
+                        ) {
+                            break;
+                        }
+                    }
                 }
             }
         });
 
-        Ok(Self { sender })
+        Ok(Self {
+            sender,
+            reader_sender,
+            in_txn,
+            changes_tx,
+            events_tx,
+        })
     }
 }
 
+/// Pure `SELECT`-like statements can safely run on any reader connection: they never
+/// mutate the database and WAL mode lets them proceed alongside the writer.
+fn is_pure_read(stmt: &Statement) -> bool {
+    matches!(stmt.kind, StmtKind::Read)
+}
+
+/// Whether a read can be handed off to the reader pool instead of the writer thread.
+/// Only safe outside of an open transaction: once a transaction is open, a reader
+/// connection would not see its uncommitted writes, and the read has to observe the
+/// writer's own view of the database instead.
+fn should_route_to_reader(is_read: bool, in_txn: bool) -> bool {
+    is_read && !in_txn
+}
+
 #[async_trait::async_trait]
 impl Database for LibSqlDb {
     async fn execute(&self, query: Statement, params: Vec<Value>) -> QueryResult {
         let (sender, receiver) = oneshot::channel();
-        let _ = self.sender.send((query, params, sender));
+        if should_route_to_reader(is_pure_read(&query), self.in_txn.load(Ordering::Relaxed)) {
+            let _ = self
+                .reader_sender
+                .send(ReadMessage::Execute(query, params, sender));
+        } else {
+            let _ = self.sender.send(Message::Execute(query, params, sender));
+        }
+        receiver
+            .await
+            .map_err(|e| QueryError::new(ErrorCode::Internal, e.to_string()))?
+    }
+
+    async fn execute_batch(
+        &self,
+        stmts: Vec<(Statement, Vec<Value>)>,
+        checks: Vec<BatchCheck>,
+    ) -> BatchResult {
+        let (sender, receiver) = oneshot::channel();
+        let _ = self
+            .sender
+            .send(Message::ExecuteBatch(stmts, checks, sender));
         receiver
             .await
             .map_err(|e| QueryError::new(ErrorCode::Internal, e.to_string()))?
     }
+
+    async fn backup_to(
+        &self,
+        dst_path: PathBuf,
+        progress: tokio::sync::mpsc::UnboundedSender<BackupProgress>,
+    ) -> BackupResult {
+        let (sender, receiver) = oneshot::channel();
+        let _ = self
+            .sender
+            .send(Message::Backup(dst_path, progress, sender));
+        receiver
+            .await
+            .map_err(|e| QueryError::new(ErrorCode::Internal, e.to_string()))?
+    }
+
+    fn subscribe_changes(&self) -> tokio::sync::broadcast::Receiver<Vec<u8>> {
+        self.changes_tx.subscribe()
+    }
+
+    fn watch(&self, table: String) -> Pin<Box<dyn Stream<Item = ChangeEvent> + Send>> {
+        let stream = BroadcastStream::new(self.events_tx.subscribe())
+            .filter_map(|msg| std::future::ready(msg.ok()))
+            .filter(move |event| std::future::ready(event.table == table));
+        Box::pin(stream)
+    }
+
+    async fn execute_stream(
+        &self,
+        query: Statement,
+        params: Vec<Value>,
+        page_size: usize,
+    ) -> tokio::sync::mpsc::UnboundedReceiver<StreamResult> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        if should_route_to_reader(is_pure_read(&query), self.in_txn.load(Ordering::Relaxed)) {
+            let _ = self
+                .reader_sender
+                .send(ReadMessage::ExecuteStream(query, params, page_size, tx));
+        } else {
+            let _ = self
+                .sender
+                .send(Message::ExecuteStream(query, params, page_size, tx));
+        }
+        rx
+    }
+
+    async fn read_blob(&self, req: BlobRead) -> BlobResult {
+        let (sender, receiver) = oneshot::channel();
+        if should_route_to_reader(true, self.in_txn.load(Ordering::Relaxed)) {
+            let _ = self.reader_sender.send(ReadMessage::ReadBlob(req, sender));
+        } else {
+            let _ = self.sender.send(Message::ReadBlob(req, sender));
+        }
+        receiver
+            .await
+            .map_err(|e| QueryError::new(ErrorCode::Internal, e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_conn() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute("create table t (id integer primary key, v text)", ())
+            .unwrap();
+        conn
+    }
+
+    fn stmt(sql: &str, kind: StmtKind) -> Statement {
+        Statement {
+            stmt: sql.to_string(),
+            kind,
+        }
+    }
+
+    /// A failing precondition check must leave no transaction open on `conn`, so the
+    /// writer thread's own bookkeeping (`state`, `in_txn`, the timeout watchdog) stays in
+    /// sync with what SQLite actually thinks is going on.
+    #[test]
+    fn failed_check_rolls_back_and_leaves_no_open_transaction() {
+        let conn = table_conn();
+        let checks = vec![BatchCheck {
+            stmt: stmt("select 1 where 0", StmtKind::Read),
+            params: vec![],
+        }];
+        let result = execute_batch(&conn, vec![], checks);
+        assert!(result.is_err());
+        conn.execute("begin", ()).expect("no transaction should be left open");
+        conn.execute("rollback", ()).unwrap();
+    }
+
+    /// A mid-batch statement error must also roll back, leaving the connection free for
+    /// the next message the writer thread processes.
+    #[test]
+    fn failed_statement_rolls_back_and_leaves_no_open_transaction() {
+        let conn = table_conn();
+        let stmts = vec![
+            (
+                stmt("insert into t (id, v) values (1, 'a')", StmtKind::Write),
+                vec![],
+            ),
+            (
+                stmt("insert into nonexistent_table (id) values (1)", StmtKind::Write),
+                vec![],
+            ),
+        ];
+        let result = execute_batch(&conn, stmts, vec![]);
+        assert!(result.is_err());
+        conn.execute("begin", ()).expect("no transaction should be left open");
+        conn.execute("rollback", ()).unwrap();
+    }
+
+    /// A batch that commits cleanly must actually leave its writes visible and no
+    /// transaction open behind it.
+    #[test]
+    fn successful_batch_commits_and_leaves_no_open_transaction() {
+        let conn = table_conn();
+        let stmts = vec![(
+            stmt("insert into t (id, v) values (1, 'a')", StmtKind::Write),
+            vec![],
+        )];
+        let result = execute_batch(&conn, stmts, vec![]);
+        assert!(result.is_ok());
+        conn.execute("begin", ()).expect("no transaction should be left open");
+        conn.execute("rollback", ()).unwrap();
+
+        let count: i64 = conn
+            .query_row("select count(*) from t", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    /// A statement cached by an earlier call must not be allowed to fail outright once
+    /// the schema it was compiled against changes out from under it; `execute_query`
+    /// must flush the cache and retry against the new schema instead of surfacing
+    /// `SQLITE_SCHEMA` to the caller.
+    #[test]
+    fn execute_query_recovers_from_a_schema_change_on_a_cached_statement() {
+        let conn = table_conn();
+        let select = stmt("select v from t", StmtKind::Read);
+
+        conn.execute("insert into t (id, v) values (1, 'a')", ())
+            .unwrap();
+        // Warm the prepared-statement cache against the original schema.
+        execute_query(&conn, &select, vec![]).unwrap();
+
+        // Invalidate the cached plan by changing the schema it was compiled against.
+        conn.execute("drop table t", ()).unwrap();
+        conn.execute("create table t (id integer primary key, v text, extra text)", ())
+            .unwrap();
+        conn.execute("insert into t (id, v, extra) values (1, 'b', 'c')", ())
+            .unwrap();
+
+        let result = execute_query(&conn, &select, vec![]);
+        assert!(
+            result.is_ok(),
+            "a stale cached statement must be retried against the new schema, not fail outright"
+        );
+    }
+
+    /// A backup that fits in a single `BACKUP_STEP_PAGES` step must still report progress
+    /// and leave the destination with every row the source had committed -- exercising
+    /// the common case of `run_backup`'s loop without needing to race it against other
+    /// messages on `receiver`.
+    #[test]
+    fn run_backup_copies_committed_data_to_the_destination() {
+        let conn = table_conn();
+        conn.execute("insert into t (id, v) values (1, 'a')", ())
+            .unwrap();
+
+        let dst_path =
+            std::env::temp_dir().join(format!("sqld_test_backup_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&dst_path);
+
+        let (_sender, receiver) = crossbeam::channel::unbounded::<Message>();
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut state = State::Start;
+        let mut timeout_deadline = None;
+        let mut timedout = false;
+        let in_txn = Arc::new(AtomicBool::new(false));
+
+        let result = run_backup(
+            &conn,
+            &dst_path,
+            &progress_tx,
+            &receiver,
+            &mut state,
+            &mut timeout_deadline,
+            &mut timedout,
+            &in_txn,
+        );
+
+        let backup_result = match result {
+            ControlFlow::Continue(r) => r,
+            ControlFlow::Break(()) => panic!("run_backup reported the writer thread should stop"),
+        };
+        assert!(backup_result.is_ok());
+        assert!(
+            progress_rx.try_recv().is_ok(),
+            "a progress update should have been sent"
+        );
+
+        let dst = rusqlite::Connection::open(&dst_path).unwrap();
+        let count: i64 = dst
+            .query_row("select count(*) from t", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let _ = std::fs::remove_file(&dst_path);
+    }
+
+    /// An explicit `ROLLBACK` must never publish a changeset, even though it closes the
+    /// transaction the same way `COMMIT` does from the `State` machine's point of view.
+    /// `register_write_hooks` drives publishing off SQLite's own commit/rollback hooks
+    /// precisely so this distinction doesn't depend on inferring it from SQL keywords.
+    #[test]
+    fn rollback_does_not_publish_a_changeset_but_commit_does() {
+        let conn = table_conn();
+        let mut session = Session::new(&conn).unwrap();
+        session.attach_all().unwrap();
+        let session = Arc::new(Mutex::new(session));
+        let (changes_tx, mut changes_rx) = tokio::sync::broadcast::channel(16);
+        let (events_tx, _events_rx) = tokio::sync::broadcast::channel(16);
+        register_write_hooks(&conn, session, changes_tx, events_tx);
+
+        conn.execute("begin", ()).unwrap();
+        conn.execute("insert into t (id, v) values (1, 'a')", ())
+            .unwrap();
+        conn.execute("rollback", ()).unwrap();
+        assert!(
+            changes_rx.try_recv().is_err(),
+            "a rolled-back transaction must not publish a changeset"
+        );
+
+        conn.execute("begin", ()).unwrap();
+        conn.execute("insert into t (id, v) values (2, 'b')", ())
+            .unwrap();
+        conn.execute("commit", ()).unwrap();
+        assert!(
+            changes_rx.try_recv().is_ok(),
+            "a committed transaction must publish a changeset"
+        );
+    }
+
+    /// A read must stay on the writer connection while a transaction is open, even if
+    /// it is itself a pure `SELECT` -- a reader-pool connection would not see the
+    /// transaction's uncommitted writes.
+    #[test]
+    fn reads_are_only_routed_to_the_reader_pool_outside_a_transaction() {
+        assert!(should_route_to_reader(true, false));
+        assert!(!should_route_to_reader(true, true));
+        assert!(!should_route_to_reader(false, false));
+        assert!(!should_route_to_reader(false, true));
+    }
 }